@@ -0,0 +1,1034 @@
+//! Generation of TypeScript definition files from [`TypeDef`] implementations.
+
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    fmt,
+    fs,
+    io::{self, Write},
+    path::Path,
+    sync::Arc,
+};
+
+use crate::type_expr::{
+    ObjectField,
+    Prim,
+    TypeExpr,
+    TypeInfo,
+    VariantExpr,
+    VariantPayload,
+};
+
+/// Implemented for every Rust type that has a corresponding TypeScript type
+/// definition.
+///
+/// This trait is usually derived with `#[derive(TypeDef)]` rather than
+/// implemented by hand; see the crate documentation for the shapes the
+/// derive macro produces for structs and enums.
+pub trait TypeDef: 'static {
+    /// Metadata about this type's definition (its name, path and docs).
+    ///
+    /// Unused when [`INLINE`](Self::INLINE) is `true`.
+    const INFO: TypeInfo;
+    /// Whether this type should be inlined at every use site instead of
+    /// being emitted as its own `export type` declaration.
+    ///
+    /// This is `true` for types like `String` and `Option<T>` that don't
+    /// benefit from a name of their own.
+    const INLINE: bool = false;
+    /// The shape of this type's definition.
+    ///
+    /// For a generic type this is the same for every instantiation: it is
+    /// expressed in terms of [`TypeExpr::TypeVar`] rather than any concrete
+    /// argument, since the `export type Wrapper<T> = ...` declaration is
+    /// only ever emitted once.
+    fn def() -> TypeExpr;
+    /// Registers the types this type's definition directly refers to.
+    ///
+    /// The derive macro generates this to call [`Deps::add`] once per field
+    /// (or variant payload) type; hand-written impls of container types
+    /// like `Vec<T>` do the same for their parameter. For a generic type
+    /// this must still be called for every instantiation (not just the
+    /// first), since it is what registers the concrete type arguments.
+    #[allow(unused_variables)]
+    fn register_deps(deps: &mut Deps) {}
+    /// The concrete type arguments this type was instantiated with, printed
+    /// as `<Arg, ...>` after the type's name when it is referenced.
+    ///
+    /// Empty for non-generic types.
+    fn generic_args() -> Vec<TypeExpr> {
+        Vec::new()
+    }
+}
+
+/// Either embeds `T`'s definition directly, or refers to it by name,
+/// depending on [`TypeDef::INLINE`].
+pub fn type_expr<T: TypeDef>() -> TypeExpr {
+    if T::INLINE {
+        T::def()
+    } else {
+        TypeExpr::Ref {
+            info: T::INFO,
+            args: T::generic_args(),
+        }
+    }
+}
+
+/// The set of type definitions a root type transitively depends on, in the
+/// order they must be emitted in (a definition always comes after the
+/// definitions it refers to).
+#[derive(Debug, Default)]
+pub struct Deps {
+    visited: HashSet<TypeInfo>,
+    stack: Vec<(TypeInfo, Vec<TypeExpr>)>,
+    recursive: HashSet<TypeInfo>,
+    ordered: Vec<(TypeInfo, TypeExpr)>,
+}
+
+impl Deps {
+    /// Registers `T` and everything it depends on. A definition is only
+    /// added once per type, and never for a [`TypeDef::INLINE`] type, whose
+    /// own dependencies (e.g. the `T` of a `Vec<T>`) are still registered.
+    ///
+    /// `T::register_deps` is walked every time, even for a type whose
+    /// definition has already been emitted: for a generic type like
+    /// `Wrapper<T>`, every distinct instantiation shares the same
+    /// definition (so it's only pushed to `ordered` once) but carries its
+    /// own concrete type argument, which still needs to be registered.
+    pub fn add<T: TypeDef>(&mut self) {
+        if T::INLINE {
+            T::register_deps(self);
+            return;
+        }
+        let info = T::INFO;
+        let args = T::generic_args();
+        let frame = (info, args);
+        if self.stack.contains(&frame) {
+            // `T` is being visited further up the call stack with the same
+            // concrete type arguments, i.e. it's part of a genuine
+            // reference cycle. Its own definition will still be pushed to
+            // `ordered` once that outer call unwinds.
+            //
+            // Comparing the full `(TypeInfo, args)` pair (rather than just
+            // `info`) matters for generics: nesting the same generic type
+            // inside itself with different arguments (e.g.
+            // `Wrapper<Wrapper<Foo>>`) shares a single `TypeInfo` between
+            // the outer and inner instantiation, but isn't a cycle, and
+            // still needs its inner argument (`Foo`) walked below.
+            self.recursive.insert(info);
+            return;
+        }
+        let already_defined = !self.visited.insert(info);
+        self.stack.push(frame);
+        T::register_deps(self);
+        self.stack.pop();
+        if !already_defined {
+            self.ordered.push((info, T::def()));
+        }
+    }
+
+    /// The registered type definitions, in dependency order.
+    pub fn ordered(&self) -> &[(TypeInfo, TypeExpr)] {
+        &self.ordered
+    }
+
+    /// Whether `info` closes a reference cycle (it was reached again while
+    /// its own dependencies were still being registered), and therefore
+    /// needs to be wrapped in `z.lazy(...)` when its Zod schema is
+    /// referenced. Other types in the same cycle are not marked.
+    pub fn is_recursive(&self, info: TypeInfo) -> bool {
+        self.recursive.contains(&info)
+    }
+}
+
+/// A pluggable policy for transforming the names emitted for types and
+/// object fields, applied independently of any `#[serde(rename)]` already
+/// baked into a type's [`TypeExpr`].
+///
+/// This is consulted for every `export type X` declaration and every
+/// reference to it (so they stay consistent), and for every JSON field
+/// key. It's needed when integrating generated types into a codebase with
+/// its own naming conventions, or to disambiguate same-named Rust types
+/// defined in different modules.
+pub trait NamePolicy: fmt::Debug {
+    /// Transforms a top-level type name.
+    fn type_name(&self, info: TypeInfo) -> Cow<'static, str> {
+        Cow::Borrowed(info.name)
+    }
+    /// Transforms an object field key.
+    fn field_name(&self, name: &str) -> Cow<'static, str> {
+        Cow::Owned(name.to_owned())
+    }
+}
+
+/// The default [`NamePolicy`]: emits names exactly as derived from the
+/// Rust source.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IdentityNames;
+
+impl NamePolicy for IdentityNames {}
+
+/// A target casing style, used by [`CasedNames`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Casing {
+    /// `SomeName`
+    PascalCase,
+    /// `someName`
+    CamelCase,
+}
+
+/// A [`NamePolicy`] that forces a fixed casing onto type names and field
+/// keys (assuming `snake_case` Rust identifiers as input), and adds a fixed
+/// prefix/suffix to every type name.
+///
+/// When a type has a non-empty [`TypeInfo::path`] (i.e. it was placed under
+/// `#[type_def(namespace = "...")]`), that path is cased the same way and
+/// folded into the name between the prefix and the type name itself, so
+/// e.g. `billing::Invoice` and `shipping::Invoice` print as
+/// `BillingInvoice` and `ShippingInvoice` rather than both printing as
+/// `Invoice`. `type_prefix`/`type_suffix` alone can't resolve that kind of
+/// collision, since they're the same fixed string for every type; two
+/// same-named types that *both* have an empty (or identical) path still
+/// print under the same name here -- nothing in [`TypeInfo`] distinguishes
+/// them any further.
+#[derive(Debug, Clone)]
+pub struct CasedNames {
+    /// The casing applied to type names.
+    pub type_casing: Casing,
+    /// The casing applied to field keys.
+    pub field_casing: Casing,
+    /// Prepended to every (cased) type name.
+    pub type_prefix: Cow<'static, str>,
+    /// Appended to every (cased) type name.
+    pub type_suffix: Cow<'static, str>,
+}
+
+impl NamePolicy for CasedNames {
+    fn type_name(&self, info: TypeInfo) -> Cow<'static, str> {
+        let mut path = String::new();
+        for segment in info.path {
+            path.push_str(&apply_casing(segment, self.type_casing));
+        }
+        Cow::Owned(format!(
+            "{}{}{}{}",
+            self.type_prefix,
+            path,
+            apply_casing(info.name, self.type_casing),
+            self.type_suffix
+        ))
+    }
+
+    fn field_name(&self, name: &str) -> Cow<'static, str> {
+        Cow::Owned(apply_casing(name, self.field_casing))
+    }
+}
+
+fn apply_casing(name: &str, casing: Casing) -> String {
+    let mut result = String::with_capacity(name.len());
+    for (i, word) in name.split('_').filter(|word| !word.is_empty()).enumerate() {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            if i == 0 && casing == Casing::CamelCase {
+                result.extend(first.to_lowercase());
+            } else {
+                result.extend(first.to_uppercase());
+            }
+            result.extend(chars);
+        }
+    }
+    result
+}
+
+/// Bundles together the per-file rendering settings that need to be
+/// threaded down into every recursive `emit_*` call.
+struct RenderCtx<'a> {
+    /// The namespace named type references are printed under (e.g.
+    /// `types.Foo`); `None` in multi-file output, where each type lives in
+    /// its own module and is referred to by its bare name instead, relying
+    /// on an `import type` at the top of the file.
+    namespace: Option<&'a str>,
+    names: &'a dyn NamePolicy,
+    /// In multi-file output, the name a referenced type is imported under
+    /// in *this* file, if it differs from `names.type_name`. Two distinct
+    /// `TypeInfo`s can print the same name (see [`file_stem`]), which would
+    /// otherwise produce two colliding `import` bindings in a file that
+    /// depends on both; `ref_name` resolves a reference through this map
+    /// before falling back to the plain name. Always empty (so always a
+    /// no-op) in single-file output, which has no imports; two types that
+    /// print under the same name there are not disambiguated.
+    aliases: &'a HashMap<TypeInfo, String>,
+}
+
+/// The name `info` is referred to by from within the file currently being
+/// rendered, accounting for any local import alias recorded in
+/// `ctx.aliases`.
+fn ref_name(ctx: &RenderCtx<'_>, info: TypeInfo) -> Cow<'static, str> {
+    match ctx.aliases.get(&info) {
+        Some(alias) => Cow::Owned(alias.clone()),
+        None => ctx.names.type_name(info),
+    }
+}
+
+/// Controls whether, and how, Zod runtime schemas are emitted alongside the
+/// plain `export type` declarations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ZodMode {
+    /// Don't emit any Zod schemas.
+    #[default]
+    Off,
+    /// Emit only the Zod schema (`export const XSchema = z...(...);`), with
+    /// the type alias derived from it via `z.infer`.
+    Only,
+    /// Emit both the plain `export type X = ...;` declaration and the Zod
+    /// schema, side by side.
+    Both,
+}
+
+/// Statistics about a generated definition file.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Stats {
+    /// The number of type definitions that were emitted.
+    pub type_definitions: usize,
+}
+
+/// Options controlling how [`write_definition_file`] renders a module.
+#[derive(Clone)]
+pub struct DefinitionFileOptions {
+    /// A header comment placed at the top of the file.
+    pub header: Cow<'static, str>,
+    /// The name of the root namespace all definitions are placed under.
+    pub root_namespace: Cow<'static, str>,
+    /// Whether, and how, to emit Zod runtime schemas alongside the
+    /// generated types.
+    pub zod: ZodMode,
+    /// Whether to emit a `isFoo(x: any): x is Foo` type-predicate function
+    /// alongside each generated type, for narrowing untyped values (e.g.
+    /// the result of `JSON.parse`) at runtime.
+    pub type_guards: bool,
+    /// Whether to emit a `HandleFoo<R>` visitor interface and a matching
+    /// `applyFoo` dispatch function for every tagged union, so that
+    /// handling a Rust enum on the TypeScript side can be checked for
+    /// exhaustiveness by the compiler.
+    pub handlers: bool,
+    /// The extension appended to `import type` specifiers written by
+    /// [`write_definition_files`] (e.g. `.js`, as required by Node's ESM
+    /// module resolution). Ignored by [`write_definition_file`].
+    pub import_extension: Cow<'static, str>,
+    /// The renaming policy applied to emitted type names and field keys.
+    pub names: Arc<dyn NamePolicy>,
+}
+
+impl Default for DefinitionFileOptions {
+    fn default() -> Self {
+        Self {
+            header: Cow::Borrowed("// AUTO-GENERATED by typescript-type-def"),
+            root_namespace: Cow::Borrowed("types"),
+            zod: ZodMode::Off,
+            type_guards: false,
+            handlers: false,
+            import_extension: Cow::Borrowed(""),
+            names: Arc::new(IdentityNames),
+        }
+    }
+}
+
+impl fmt::Debug for DefinitionFileOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DefinitionFileOptions")
+            .field("header", &self.header)
+            .field("root_namespace", &self.root_namespace)
+            .field("zod", &self.zod)
+            .field("type_guards", &self.type_guards)
+            .field("handlers", &self.handlers)
+            .field("import_extension", &self.import_extension)
+            .field("names", &self.names)
+            .finish()
+    }
+}
+
+/// Writes a TypeScript definition file describing `T` (and everything it
+/// depends on) to `out`.
+pub fn write_definition_file<W, T>(
+    mut out: W,
+    options: DefinitionFileOptions,
+) -> io::Result<Stats>
+where
+    W: Write,
+    T: TypeDef,
+{
+    let mut deps = Deps::default();
+    deps.add::<T>();
+    let no_aliases = HashMap::new();
+    let ctx = RenderCtx {
+        namespace: Some(&options.root_namespace),
+        names: &*options.names,
+        aliases: &no_aliases,
+    };
+    writeln!(out, "{}", options.header)?;
+    writeln!(out)?;
+    if options.zod != ZodMode::Off {
+        writeln!(out, "import {{ z }} from \"zod\";")?;
+        writeln!(out)?;
+    }
+    writeln!(out, "export default {};", options.root_namespace)?;
+    writeln!(out, "export namespace {}{{", options.root_namespace)?;
+    for (info, def) in deps.ordered() {
+        emit_type_def(&mut out, &ctx, &deps, *info, def, options.zod)?;
+        if options.type_guards {
+            emit_type_guard(&mut out, &ctx, *info, def)?;
+        }
+        if options.handlers {
+            if let TypeExpr::Union { tag, variants } = def {
+                emit_handler(&mut out, &ctx, *info, tag, variants)?;
+            }
+        }
+    }
+    writeln!(out, "}}")?;
+    Ok(Stats {
+        type_definitions: deps.ordered().len(),
+    })
+}
+
+/// Writes one `.ts` file per top-level type definition describing `T` (and
+/// everything it depends on) into `dir`, with `import type` statements
+/// generated from the cross-type dependency edges in [`Deps`].
+///
+/// This matches how large frontends tend to consume generated bindings:
+/// one tree-shakeable module per type, instead of one namespaced file
+/// containing everything.
+pub fn write_definition_files<T>(
+    dir: impl AsRef<Path>,
+    options: DefinitionFileOptions,
+) -> io::Result<Stats>
+where
+    T: TypeDef,
+{
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+    let mut deps = Deps::default();
+    deps.add::<T>();
+    let names = &*options.names;
+    for (info, def) in deps.ordered() {
+        let mut out = Vec::new();
+        writeln!(out, "{}", options.header)?;
+        writeln!(out)?;
+        let own_name = names.type_name(*info);
+        let mut imports = collect_refs(def);
+        // Compare on the full `TypeInfo` (not just `name`): two distinct
+        // Rust types defined in different modules can share a bare name,
+        // and deduping/filtering on `name` alone would either drop one of
+        // their imports entirely or, via the `.ts` filename below, clobber
+        // one type's file with the other's.
+        imports.sort_by_key(|dep| (dep.path, dep.name));
+        imports.dedup();
+        imports.retain(|dep| dep != info);
+        // Two distinct Rust types can still print under the same name via
+        // `names.type_name` (that's exactly the case `file_stem` qualifies
+        // the *filename* with `path` to keep separate); if this file
+        // imports two such same-named deps, give every import after the
+        // first a `file_stem`-derived alias (suffixed with `_` until it is
+        // unique) so their `import` bindings don't collide with each other
+        // or with `own_name`.
+        let mut used_names = HashSet::new();
+        used_names.insert(own_name.clone().into_owned());
+        let mut aliases = HashMap::new();
+        for dep in &imports {
+            let dep_name = names.type_name(*dep).into_owned();
+            if !used_names.insert(dep_name.clone()) {
+                let mut alias = file_stem(*dep, names).replace('.', "_");
+                while !used_names.insert(alias.clone()) {
+                    alias.push('_');
+                }
+                aliases.insert(*dep, alias);
+            }
+        }
+        let ctx = RenderCtx {
+            namespace: None,
+            names,
+            aliases: &aliases,
+        };
+        let mut wrote_import = false;
+        if options.zod != ZodMode::Off {
+            writeln!(out, "import {{ z }} from \"zod\";")?;
+            wrote_import = true;
+        }
+        for dep in &imports {
+            let dep_name = names.type_name(*dep);
+            let dep_stem = file_stem(*dep, names);
+            let local_name = ref_name(&ctx, *dep);
+            write!(out, "import type {{ {}", dep_name)?;
+            if local_name != dep_name {
+                write!(out, " as {}", local_name)?;
+            }
+            writeln!(out, " }} from \"./{}{}\";", dep_stem, options.import_extension)?;
+            // The type declaration is only half of what a dependent file
+            // may reference: the runtime Zod schema and type-guard
+            // function `emit_zod_expr`/`write_guard_cond` generate calls
+            // to (`{Name}Schema`, `is{Name}`) are values, not types, and
+            // need their own non-`type` import, under the same local
+            // alias (if any) as the type itself.
+            let mut values = Vec::new();
+            if options.zod != ZodMode::Off {
+                values.push((format!("{}Schema", dep_name), format!("{}Schema", local_name)));
+            }
+            if options.type_guards {
+                values.push((format!("is{}", dep_name), format!("is{}", local_name)));
+            }
+            if !values.is_empty() {
+                write!(out, "import {{ ")?;
+                for (i, (exported, local)) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(out, ", ")?;
+                    }
+                    write!(out, "{}", exported)?;
+                    if local != exported {
+                        write!(out, " as {}", local)?;
+                    }
+                }
+                writeln!(out, " }} from \"./{}{}\";", dep_stem, options.import_extension)?;
+            }
+            wrote_import = true;
+        }
+        if wrote_import {
+            writeln!(out)?;
+        }
+        emit_type_def(&mut out, &ctx, &deps, *info, def, options.zod)?;
+        if options.type_guards {
+            emit_type_guard(&mut out, &ctx, *info, def)?;
+        }
+        if options.handlers {
+            if let TypeExpr::Union { tag, variants } = def {
+                emit_handler(&mut out, &ctx, *info, tag, variants)?;
+            }
+        }
+        fs::write(dir.join(format!("{}.ts", file_stem(*info, names))), out)?;
+    }
+    Ok(Stats {
+        type_definitions: deps.ordered().len(),
+    })
+}
+
+/// The base name [`write_definition_files`] writes `info`'s module under
+/// (i.e. `{file_stem}.ts`), and that other modules import it by.
+///
+/// This is deliberately *not* just `names.type_name(info)`: a [`NamePolicy`]
+/// only controls the name a type is printed under inside its own file, and
+/// two distinct Rust types nested under different `#[type_def(namespace =
+/// ...)]` paths can still print under the same name there. Qualifying the
+/// filename with `info.path` keeps their modules from colliding, even
+/// though nothing can be done about two same-named types that also share
+/// the same (or no) path -- that case isn't resolvable from the
+/// information [`TypeInfo`] carries.
+fn file_stem(info: TypeInfo, names: &dyn NamePolicy) -> String {
+    let name = names.type_name(info);
+    if info.path.is_empty() {
+        name.into_owned()
+    } else {
+        format!("{}.{}", info.path.join("."), name)
+    }
+}
+
+/// Collects the set of named types directly referenced by `expr`'s own
+/// printed form (not transitively), used to compute per-file imports.
+fn collect_refs(expr: &TypeExpr) -> Vec<TypeInfo> {
+    let mut refs = Vec::new();
+    collect_refs_into(expr, &mut refs);
+    refs
+}
+
+fn collect_refs_into(expr: &TypeExpr, refs: &mut Vec<TypeInfo>) {
+    match expr {
+        TypeExpr::Ref { info, args } => {
+            refs.push(*info);
+            for arg in args {
+                collect_refs_into(arg, refs);
+            }
+        }
+        TypeExpr::TypeVar(_) => {}
+        TypeExpr::Prim(_) => {}
+        TypeExpr::Object(fields) => {
+            for field in fields {
+                collect_refs_into(&field.r#type, refs);
+            }
+        }
+        TypeExpr::Array(elem) | TypeExpr::Option(elem) => collect_refs_into(elem, refs),
+        TypeExpr::Tuple(elems) => {
+            for elem in elems {
+                collect_refs_into(elem, refs);
+            }
+        }
+        TypeExpr::Map(key, value) => {
+            collect_refs_into(key, refs);
+            collect_refs_into(value, refs);
+        }
+        TypeExpr::Union { variants, .. } => {
+            for variant in variants {
+                match &variant.payload {
+                    VariantPayload::Unit => {}
+                    VariantPayload::Newtype { payload, .. } => collect_refs_into(payload, refs),
+                    VariantPayload::Fields(fields) => {
+                        for field in fields {
+                            collect_refs_into(&field.r#type, refs);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn emit_type_def<W: Write>(
+    out: &mut W,
+    ctx: &RenderCtx<'_>,
+    deps: &Deps,
+    info: TypeInfo,
+    def: &TypeExpr,
+    zod: ZodMode,
+) -> io::Result<()> {
+    if let Some(docs) = info.docs {
+        writeln!(out, "/** {} */", docs)?;
+    }
+    let name = ctx.names.type_name(info);
+    let generic_params = generic_params_suffix(info);
+    match zod {
+        ZodMode::Off => {
+            write!(out, "export type {}{}=", name, generic_params)?;
+            emit_type_expr(out, ctx, def)?;
+            writeln!(out, ";")?;
+        }
+        ZodMode::Only => {
+            write!(out, "export const {}Schema=", name)?;
+            emit_zod_expr(out, ctx, deps, def)?;
+            writeln!(out, ";")?;
+            writeln!(
+                out,
+                "export type {}{}=z.infer<typeof {}Schema>;",
+                name, generic_params, name
+            )?;
+        }
+        ZodMode::Both => {
+            write!(out, "export type {}{}=", name, generic_params)?;
+            emit_type_expr(out, ctx, def)?;
+            writeln!(out, ";")?;
+            write!(out, "export const {}Schema=", name)?;
+            emit_zod_expr(out, ctx, deps, def)?;
+            writeln!(out, ";")?;
+        }
+    }
+    Ok(())
+}
+
+/// Formats `info`'s generic parameters as `<T,U>`, or an empty string if
+/// it isn't generic.
+fn generic_params_suffix(info: TypeInfo) -> String {
+    if info.generic_params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", info.generic_params.join(","))
+    }
+}
+
+/// Prints `expr` as TypeScript syntax.
+///
+/// `ctx.namespace` is the namespace named type references are printed
+/// under (e.g. `types.Foo`); it is `None` in multi-file output, where each
+/// type lives in its own module and is referred to by its bare name
+/// instead, relying on an `import type` at the top of the file.
+/// `ctx.names` renames type and field names on the way out.
+fn emit_type_expr<W: Write>(
+    out: &mut W,
+    ctx: &RenderCtx<'_>,
+    expr: &TypeExpr,
+) -> io::Result<()> {
+    match expr {
+        TypeExpr::Ref { info, args } => {
+            let name = ref_name(ctx, *info);
+            match ctx.namespace {
+                Some(namespace) => write!(out, "{}.{}", namespace, name)?,
+                None => write!(out, "{}", name)?,
+            }
+            if !args.is_empty() {
+                write!(out, "<")?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(out, ",")?;
+                    }
+                    emit_type_expr(out, ctx, arg)?;
+                }
+                write!(out, ">")?;
+            }
+            Ok(())
+        }
+        TypeExpr::TypeVar(name) => write!(out, "{}", name),
+        TypeExpr::Prim(prim) => write!(out, "{}", prim_name(*prim)),
+        TypeExpr::Object(fields) => {
+            write!(out, "{{")?;
+            for field in fields {
+                emit_object_field(out, ctx, field, emit_type_expr)?;
+            }
+            write!(out, "}}")
+        }
+        TypeExpr::Array(elem) => {
+            write!(out, "(")?;
+            emit_type_expr(out, ctx, elem)?;
+            write!(out, ")[]")
+        }
+        TypeExpr::Tuple(elems) => {
+            write!(out, "[")?;
+            for (i, elem) in elems.iter().enumerate() {
+                if i > 0 {
+                    write!(out, ",")?;
+                }
+                emit_type_expr(out, ctx, elem)?;
+            }
+            write!(out, "]")
+        }
+        TypeExpr::Option(inner) => {
+            emit_type_expr(out, ctx, inner)?;
+            write!(out, "|null")
+        }
+        TypeExpr::Map(key, value) => {
+            write!(out, "Record<")?;
+            emit_type_expr(out, ctx, key)?;
+            write!(out, ",")?;
+            emit_type_expr(out, ctx, value)?;
+            write!(out, ">")
+        }
+        TypeExpr::Union { tag, variants } => {
+            for (i, variant) in variants.iter().enumerate() {
+                if i > 0 {
+                    write!(out, "|")?;
+                }
+                write!(out, "{{\"{}\":\"{}\";", tag, variant.tag_value)?;
+                match &variant.payload {
+                    VariantPayload::Unit => {}
+                    VariantPayload::Newtype { content, payload } => {
+                        write!(out, "\"{}\":", content)?;
+                        emit_type_expr(out, ctx, payload)?;
+                        write!(out, ";")?;
+                    }
+                    VariantPayload::Fields(fields) => {
+                        for field in fields {
+                            emit_object_field(out, ctx, field, emit_type_expr)?;
+                        }
+                    }
+                }
+                write!(out, "}}")?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn emit_object_field<W: Write>(
+    out: &mut W,
+    ctx: &RenderCtx<'_>,
+    field: &ObjectField,
+    mut emit_inner: impl FnMut(&mut W, &RenderCtx<'_>, &TypeExpr) -> io::Result<()>,
+) -> io::Result<()> {
+    write!(
+        out,
+        "\"{}\"{}:",
+        ctx.names.field_name(&field.name),
+        if field.optional { "?" } else { "" }
+    )?;
+    emit_inner(out, ctx, &field.r#type)?;
+    write!(out, ";")
+}
+
+fn emit_zod_expr<W: Write>(
+    out: &mut W,
+    ctx: &RenderCtx<'_>,
+    deps: &Deps,
+    expr: &TypeExpr,
+) -> io::Result<()> {
+    match expr {
+        // Zod schemas aren't parameterized, so a reference to a generic
+        // type's schema just ignores its type arguments.
+        TypeExpr::Ref { info, .. } => {
+            let name = ref_name(ctx, *info);
+            if deps.is_recursive(*info) {
+                write!(out, "z.lazy(()=>{}Schema)", name)
+            } else {
+                write!(out, "{}Schema", name)
+            }
+        }
+        // A bare generic parameter has no statically known shape to
+        // validate.
+        TypeExpr::TypeVar(_) => write!(out, "z.any()"),
+        TypeExpr::Prim(prim) => write!(out, "{}", zod_prim(*prim)),
+        TypeExpr::Object(fields) => {
+            write!(out, "z.object({{")?;
+            for field in fields {
+                write!(out, "\"{}\":", ctx.names.field_name(&field.name))?;
+                emit_zod_expr(out, ctx, deps, &field.r#type)?;
+                if field.optional {
+                    write!(out, ".optional()")?;
+                }
+                write!(out, ",")?;
+            }
+            write!(out, "}})")
+        }
+        TypeExpr::Array(elem) => {
+            write!(out, "z.array(")?;
+            emit_zod_expr(out, ctx, deps, elem)?;
+            write!(out, ")")
+        }
+        TypeExpr::Tuple(elems) => {
+            write!(out, "z.tuple([")?;
+            for (i, elem) in elems.iter().enumerate() {
+                if i > 0 {
+                    write!(out, ",")?;
+                }
+                emit_zod_expr(out, ctx, deps, elem)?;
+            }
+            write!(out, "])")
+        }
+        TypeExpr::Option(inner) => {
+            emit_zod_expr(out, ctx, deps, inner)?;
+            write!(out, ".nullable()")
+        }
+        TypeExpr::Map(key, value) => {
+            write!(out, "z.record(")?;
+            emit_zod_expr(out, ctx, deps, key)?;
+            write!(out, ",")?;
+            emit_zod_expr(out, ctx, deps, value)?;
+            write!(out, ")")
+        }
+        TypeExpr::Union { tag, variants } => {
+            write!(out, "z.discriminatedUnion(\"{}\",[", tag)?;
+            for (i, variant) in variants.iter().enumerate() {
+                if i > 0 {
+                    write!(out, ",")?;
+                }
+                write!(
+                    out,
+                    "z.object({{\"{}\":z.literal(\"{}\"),",
+                    tag, variant.tag_value
+                )?;
+                match &variant.payload {
+                    VariantPayload::Unit => {}
+                    VariantPayload::Newtype { content, payload } => {
+                        write!(out, "\"{}\":", content)?;
+                        emit_zod_expr(out, ctx, deps, payload)?;
+                        write!(out, ",")?;
+                    }
+                    VariantPayload::Fields(fields) => {
+                        for field in fields {
+                            write!(out, "\"{}\":", ctx.names.field_name(&field.name))?;
+                            emit_zod_expr(out, ctx, deps, &field.r#type)?;
+                            if field.optional {
+                                write!(out, ".optional()")?;
+                            }
+                            write!(out, ",")?;
+                        }
+                    }
+                }
+                write!(out, "}})")?;
+            }
+            write!(out, "])")
+        }
+    }
+}
+
+fn emit_type_guard<W: Write>(
+    out: &mut W,
+    ctx: &RenderCtx<'_>,
+    info: TypeInfo,
+    def: &TypeExpr,
+) -> io::Result<()> {
+    let name = ctx.names.type_name(info);
+    write!(
+        out,
+        "export function is{}(x: any): x is {} {{return ",
+        name, name
+    )?;
+    write_guard_cond(out, ctx, def, "x")?;
+    writeln!(out, ";}}")
+}
+
+/// Writes a boolean JavaScript expression that tests whether `var_name`
+/// matches the shape described by `expr`.
+fn write_guard_cond<W: Write>(
+    out: &mut W,
+    ctx: &RenderCtx<'_>,
+    expr: &TypeExpr,
+    var_name: &str,
+) -> io::Result<()> {
+    match expr {
+        // A generic type's guard ignores its type arguments, for the same
+        // reason its Zod schema does.
+        TypeExpr::Ref { info, .. } => write!(out, "is{}({})", ref_name(ctx, *info), var_name),
+        // No runtime check is possible against an unconstrained generic
+        // parameter.
+        TypeExpr::TypeVar(_) => write!(out, "true"),
+        TypeExpr::Prim(Prim::Number) => write!(out, "typeof {} === \"number\"", var_name),
+        TypeExpr::Prim(Prim::String) => write!(out, "typeof {} === \"string\"", var_name),
+        TypeExpr::Prim(Prim::Boolean) => write!(out, "typeof {} === \"boolean\"", var_name),
+        TypeExpr::Prim(Prim::Null) => write!(out, "{} === null", var_name),
+        TypeExpr::Prim(Prim::Any) => write!(out, "true"),
+        TypeExpr::Object(fields) => {
+            write!(out, "(typeof {} === \"object\" && {} !== null", var_name, var_name)?;
+            for field in fields {
+                write!(out, " && ")?;
+                let field_var = format!(
+                    "{}[\"{}\"]",
+                    var_name,
+                    ctx.names.field_name(&field.name)
+                );
+                if field.optional {
+                    write!(out, "({} === undefined || ", field_var)?;
+                    write_guard_cond(out, ctx, &field.r#type, &field_var)?;
+                    write!(out, ")")?;
+                } else {
+                    write_guard_cond(out, ctx, &field.r#type, &field_var)?;
+                }
+            }
+            write!(out, ")")
+        }
+        TypeExpr::Array(elem) => {
+            write!(out, "(Array.isArray({}) && {}.every(x => ", var_name, var_name)?;
+            write_guard_cond(out, ctx, elem, "x")?;
+            write!(out, "))")
+        }
+        TypeExpr::Tuple(elems) => {
+            write!(
+                out,
+                "(Array.isArray({}) && {}.length === {}",
+                var_name,
+                var_name,
+                elems.len()
+            )?;
+            for (i, elem) in elems.iter().enumerate() {
+                write!(out, " && ")?;
+                write_guard_cond(out, ctx, elem, &format!("{}[{}]", var_name, i))?;
+            }
+            write!(out, ")")
+        }
+        TypeExpr::Option(inner) => {
+            write!(out, "({} === null || ", var_name)?;
+            write_guard_cond(out, ctx, inner, var_name)?;
+            write!(out, ")")
+        }
+        TypeExpr::Map(_key, value) => {
+            write!(
+                out,
+                "(typeof {} === \"object\" && {} !== null && Object.values({}).every(x => ",
+                var_name, var_name, var_name
+            )?;
+            write_guard_cond(out, ctx, value, "x")?;
+            write!(out, "))")
+        }
+        TypeExpr::Union { tag, variants } => {
+            write!(out, "(typeof {} === \"object\" && {} !== null && (", var_name, var_name)?;
+            for (i, variant) in variants.iter().enumerate() {
+                if i > 0 {
+                    write!(out, " || ")?;
+                }
+                write!(out, "({}[\"{}\"] === \"{}\"", var_name, tag, variant.tag_value)?;
+                match &variant.payload {
+                    VariantPayload::Unit => {}
+                    VariantPayload::Newtype { content, payload } => {
+                        write!(out, " && ")?;
+                        write_guard_cond(
+                            out,
+                            ctx,
+                            payload,
+                            &format!("{}[\"{}\"]", var_name, content),
+                        )?;
+                    }
+                    VariantPayload::Fields(fields) => {
+                        for field in fields {
+                            write!(out, " && ")?;
+                            let field_var = format!(
+                                "{}[\"{}\"]",
+                                var_name,
+                                ctx.names.field_name(&field.name)
+                            );
+                            if field.optional {
+                                write!(out, "({} === undefined || ", field_var)?;
+                                write_guard_cond(out, ctx, &field.r#type, &field_var)?;
+                                write!(out, ")")?;
+                            } else {
+                                write_guard_cond(out, ctx, &field.r#type, &field_var)?;
+                            }
+                        }
+                    }
+                }
+                write!(out, ")")?;
+            }
+            write!(out, "))")
+        }
+    }
+}
+
+fn emit_handler<W: Write>(
+    out: &mut W,
+    ctx: &RenderCtx<'_>,
+    info: TypeInfo,
+    tag: &str,
+    variants: &[VariantExpr],
+) -> io::Result<()> {
+    let name = ctx.names.type_name(info);
+    writeln!(out, "export interface Handle{}<R> {{", name)?;
+    for variant in variants {
+        write!(out, "{}(", handler_method_name(variant))?;
+        match &variant.payload {
+            VariantPayload::Unit => {}
+            VariantPayload::Newtype { payload, .. } => {
+                write!(out, "x: ")?;
+                emit_type_expr(out, ctx, payload)?;
+            }
+            VariantPayload::Fields(fields) => {
+                write!(out, "x: {{")?;
+                for field in fields {
+                    emit_object_field(out, ctx, field, emit_type_expr)?;
+                }
+                write!(out, "}}")?;
+            }
+        }
+        writeln!(out, "): R;")?;
+    }
+    writeln!(out, "}}")?;
+    writeln!(
+        out,
+        "export function apply{}<R>(handler: Handle{}<R>, input: {}): R {{",
+        name, name, name
+    )?;
+    writeln!(out, "switch (input[\"{}\"]) {{", tag)?;
+    for variant in variants {
+        write!(out, "case \"{}\": return handler.{}(", variant.tag_value, handler_method_name(variant))?;
+        match &variant.payload {
+            VariantPayload::Unit => {}
+            VariantPayload::Newtype { content, .. } => write!(out, "input[\"{}\"]", content)?,
+            VariantPayload::Fields(_) => write!(out, "input")?,
+        }
+        writeln!(out, ");")?;
+    }
+    writeln!(out, "}}")?;
+    writeln!(out, "}}")
+}
+
+fn handler_method_name(variant: &VariantExpr) -> String {
+    format!("on{}", variant.name)
+}
+
+fn prim_name(prim: Prim) -> &'static str {
+    match prim {
+        Prim::Number => "number",
+        Prim::String => "string",
+        Prim::Boolean => "boolean",
+        Prim::Null => "null",
+        Prim::Any => "any",
+    }
+}
+
+fn zod_prim(prim: Prim) -> &'static str {
+    match prim {
+        Prim::Number => "z.number()",
+        Prim::String => "z.string()",
+        Prim::Boolean => "z.boolean()",
+        Prim::Null => "z.null()",
+        Prim::Any => "z.any()",
+    }
+}
@@ -0,0 +1,131 @@
+//! [`TypeDef`] implementations for standard library and common external
+//! types.
+
+use crate::{
+    emit::{type_expr, Deps, TypeDef},
+    type_expr::{Prim, TypeExpr, TypeInfo},
+};
+
+macro_rules! impl_number {
+    ($ty:ty, $name:literal) => {
+        impl TypeDef for $ty {
+            const INFO: TypeInfo = TypeInfo {
+                path: &[],
+                name: $name,
+                docs: None,
+                generic_params: &[],
+            };
+            fn def() -> TypeExpr {
+                TypeExpr::Prim(Prim::Number)
+            }
+        }
+    };
+}
+impl_number!(u8, "U8");
+impl_number!(u16, "U16");
+impl_number!(u32, "U32");
+impl_number!(u64, "U64");
+impl_number!(usize, "Usize");
+impl_number!(i8, "I8");
+impl_number!(i16, "I16");
+impl_number!(i32, "I32");
+impl_number!(i64, "I64");
+impl_number!(isize, "Isize");
+impl_number!(f32, "F32");
+impl_number!(f64, "F64");
+
+impl TypeDef for String {
+    const INFO: TypeInfo = TypeInfo {
+        path: &[],
+        name: "String",
+        docs: None,
+        generic_params: &[],
+    };
+    const INLINE: bool = true;
+    fn def() -> TypeExpr {
+        TypeExpr::Prim(Prim::String)
+    }
+}
+
+impl TypeDef for bool {
+    const INFO: TypeInfo = TypeInfo {
+        path: &[],
+        name: "Boolean",
+        docs: None,
+        generic_params: &[],
+    };
+    const INLINE: bool = true;
+    fn def() -> TypeExpr {
+        TypeExpr::Prim(Prim::Boolean)
+    }
+}
+
+impl<T: TypeDef> TypeDef for Option<T> {
+    const INFO: TypeInfo = TypeInfo {
+        path: &[],
+        name: "Option",
+        docs: None,
+        generic_params: &[],
+    };
+    const INLINE: bool = true;
+    fn def() -> TypeExpr {
+        TypeExpr::Option(Box::new(type_expr::<T>()))
+    }
+    fn register_deps(deps: &mut Deps) {
+        deps.add::<T>();
+    }
+}
+
+impl<T: TypeDef> TypeDef for Vec<T> {
+    const INFO: TypeInfo = TypeInfo {
+        path: &[],
+        name: "Vec",
+        docs: None,
+        generic_params: &[],
+    };
+    const INLINE: bool = true;
+    fn def() -> TypeExpr {
+        TypeExpr::Array(Box::new(type_expr::<T>()))
+    }
+    fn register_deps(deps: &mut Deps) {
+        deps.add::<T>();
+    }
+}
+
+impl<K: TypeDef, V: TypeDef> TypeDef for std::collections::HashMap<K, V> {
+    const INFO: TypeInfo = TypeInfo {
+        path: &[],
+        name: "HashMap",
+        docs: None,
+        generic_params: &[],
+    };
+    const INLINE: bool = true;
+    fn def() -> TypeExpr {
+        TypeExpr::Map(Box::new(type_expr::<K>()), Box::new(type_expr::<V>()))
+    }
+    fn register_deps(deps: &mut Deps) {
+        deps.add::<K>();
+        deps.add::<V>();
+    }
+}
+
+/// A binary blob, represented in JSON (and therefore in TypeScript) as a
+/// base64-encoded string.
+///
+/// Serializing and deserializing this type is not handled by this crate;
+/// pair it with a `serde`-compatible base64 wrapper such as
+/// `serde_with::base64::Base64`.
+pub struct Blob(pub Vec<u8>);
+
+impl TypeDef for Blob {
+    const INFO: TypeInfo = TypeInfo {
+        path: &[],
+        name: "String",
+        docs: None,
+        generic_params: &[],
+    };
+    const INLINE: bool = true;
+    fn def() -> TypeExpr {
+        TypeExpr::Prim(Prim::String)
+    }
+}
@@ -98,6 +98,387 @@
 //! "#
 //! );
 //! ```
+//!
+//! Setting [`DefinitionFileOptions::zod`] to [`ZodMode::Both`] emits a
+//! runtime [Zod](https://zod.dev/) schema alongside each type, so untyped
+//! JSON (e.g. `JSON.parse` output) can be validated instead of merely cast:
+//! ```
+//! use serde::Serialize;
+//! use typescript_type_def::{write_definition_file, DefinitionFileOptions, TypeDef, ZodMode};
+//!
+//! #[derive(Serialize, TypeDef)]
+//! struct Foo {
+//!     a: usize,
+//!     b: String,
+//! }
+//!
+//! let ts_module = {
+//!     let mut buf = Vec::new();
+//!     let options = DefinitionFileOptions {
+//!         zod: ZodMode::Both,
+//!         ..Default::default()
+//!     };
+//!     write_definition_file::<_, Foo>(&mut buf, options).unwrap();
+//!     String::from_utf8(buf).unwrap()
+//! };
+//! assert_eq!(
+//!     ts_module,
+//!     r#"// AUTO-GENERATED by typescript-type-def
+//!
+//! import { z } from "zod";
+//!
+//! export default types;
+//! export namespace types{
+//! export type Usize=number;
+//! export const UsizeSchema=z.number();
+//! export type Foo={"a":types.Usize;"b":string;};
+//! export const FooSchema=z.object({"a":UsizeSchema,"b":z.string(),});
+//! }
+//! "#
+//! );
+//! ```
+//!
+//! Setting [`DefinitionFileOptions::type_guards`] emits a
+//! `isFoo(x: any): x is Foo` predicate function alongside each type, for
+//! narrowing an untyped value (e.g. the result of `JSON.parse`) at runtime:
+//! ```
+//! use serde::Serialize;
+//! use typescript_type_def::{write_definition_file, DefinitionFileOptions, TypeDef};
+//!
+//! #[derive(Serialize, TypeDef)]
+//! struct Foo {
+//!     a: usize,
+//!     b: String,
+//! }
+//!
+//! let ts_module = {
+//!     let mut buf = Vec::new();
+//!     let options = DefinitionFileOptions {
+//!         type_guards: true,
+//!         ..Default::default()
+//!     };
+//!     write_definition_file::<_, Foo>(&mut buf, options).unwrap();
+//!     String::from_utf8(buf).unwrap()
+//! };
+//! assert_eq!(
+//!     ts_module,
+//!     r#"// AUTO-GENERATED by typescript-type-def
+//!
+//! export default types;
+//! export namespace types{
+//! export type Usize=number;
+//! export function isUsize(x: any): x is Usize {return typeof x === "number";}
+//! export type Foo={"a":types.Usize;"b":string;};
+//! export function isFoo(x: any): x is Foo {return (typeof x === "object" && x !== null && isUsize(x["a"]) && typeof x["b"] === "string");}
+//! }
+//! "#
+//! );
+//! ```
+//!
+//! Setting [`DefinitionFileOptions::handlers`] emits a `HandleFoo<R>`
+//! visitor interface and a matching `applyFoo` dispatch function for every
+//! tagged union, so the TypeScript compiler can check that handling a Rust
+//! enum is exhaustive:
+//! ```
+//! use serde::Serialize;
+//! use typescript_type_def::{write_definition_file, DefinitionFileOptions, TypeDef};
+//!
+//! #[derive(Serialize, TypeDef)]
+//! #[serde(tag = "type", content = "content")]
+//! enum Event {
+//!     Start,
+//!     Message(String),
+//! }
+//!
+//! let ts_module = {
+//!     let mut buf = Vec::new();
+//!     let options = DefinitionFileOptions {
+//!         handlers: true,
+//!         ..Default::default()
+//!     };
+//!     write_definition_file::<_, Event>(&mut buf, options).unwrap();
+//!     String::from_utf8(buf).unwrap()
+//! };
+//! assert_eq!(
+//!     ts_module,
+//!     r#"// AUTO-GENERATED by typescript-type-def
+//!
+//! export default types;
+//! export namespace types{
+//! export type Event={"type":"Start";}|{"type":"Message";"content":string;};
+//! export interface HandleEvent<R> {
+//! onStart(): R;
+//! onMessage(x: string): R;
+//! }
+//! export function applyEvent<R>(handler: HandleEvent<R>, input: Event): R {
+//! switch (input["type"]) {
+//! case "Start": return handler.onStart();
+//! case "Message": return handler.onMessage(input["content"]);
+//! }
+//! }
+//! }
+//! "#
+//! );
+//! ```
+//!
+//! [`write_definition_files`] writes one `.ts` file per top-level type
+//! instead of a single namespaced file, with `import type` statements
+//! computed from the dependency graph:
+//! ```
+//! use serde::Serialize;
+//! use typescript_type_def::{write_definition_files, DefinitionFileOptions, TypeDef};
+//!
+//! #[derive(Serialize, TypeDef)]
+//! struct Qux {
+//!     a: String,
+//! }
+//!
+//! #[derive(Serialize, TypeDef)]
+//! struct Baz {
+//!     a: Qux,
+//! }
+//!
+//! let dir = std::env::temp_dir().join("typescript_type_def_doctest_write_definition_files");
+//! let options = DefinitionFileOptions {
+//!     import_extension: ".js".into(),
+//!     ..Default::default()
+//! };
+//! write_definition_files::<Baz>(&dir, options).unwrap();
+//! assert_eq!(
+//!     std::fs::read_to_string(dir.join("Qux.ts")).unwrap(),
+//!     r#"// AUTO-GENERATED by typescript-type-def
+//!
+//! export type Qux={"a":string;};
+//! "#
+//! );
+//! assert_eq!(
+//!     std::fs::read_to_string(dir.join("Baz.ts")).unwrap(),
+//!     r#"// AUTO-GENERATED by typescript-type-def
+//!
+//! import type { Qux } from "./Qux.js";
+//!
+//! export type Baz={"a":Qux;};
+//! "#
+//! );
+//! # std::fs::remove_dir_all(&dir).unwrap();
+//! ```
+//!
+//! Combined with [`DefinitionFileOptions::zod`] or
+//! [`DefinitionFileOptions::type_guards`], a dependent file also imports
+//! the runtime values (`{Name}Schema`, `is{Name}`) its own generated code
+//! calls into, alongside the `import type`:
+//! ```
+//! use serde::Serialize;
+//! use typescript_type_def::{write_definition_files, DefinitionFileOptions, TypeDef, ZodMode};
+//!
+//! #[derive(Serialize, TypeDef)]
+//! struct Qux {
+//!     a: String,
+//! }
+//!
+//! #[derive(Serialize, TypeDef)]
+//! struct Baz {
+//!     a: Qux,
+//! }
+//!
+//! let dir = std::env::temp_dir().join("typescript_type_def_doctest_write_definition_files_values");
+//! let options = DefinitionFileOptions {
+//!     zod: ZodMode::Both,
+//!     type_guards: true,
+//!     import_extension: ".js".into(),
+//!     ..Default::default()
+//! };
+//! write_definition_files::<Baz>(&dir, options).unwrap();
+//! assert_eq!(
+//!     std::fs::read_to_string(dir.join("Baz.ts")).unwrap(),
+//!     r#"// AUTO-GENERATED by typescript-type-def
+//!
+//! import { z } from "zod";
+//! import type { Qux } from "./Qux.js";
+//! import { QuxSchema, isQux } from "./Qux.js";
+//!
+//! export type Baz={"a":Qux;};
+//! export const BazSchema=z.object({"a":QuxSchema,});
+//! export function isBaz(x: any): x is Baz {return (typeof x === "object" && x !== null && isQux(x["a"]));}
+//! "#
+//! );
+//! # std::fs::remove_dir_all(&dir).unwrap();
+//! ```
+//!
+//! Generic types are supported at the [`type_expr`](crate::type_expr)
+//! level, with a generic type's own definition expressed in terms of
+//! [`TypeExpr::TypeVar`](crate::type_expr::TypeExpr::TypeVar) rather than
+//! any one instantiation's concrete arguments, so it's only emitted once
+//! no matter how many different arguments it's used with. The
+//! `#[derive(TypeDef)]` macro doesn't yet generate this for generic Rust
+//! types, so for now a generic type's [`TypeDef`] impl must be written by
+//! hand:
+//! ```
+//! use serde::Serialize;
+//! use typescript_type_def::{
+//!     write_definition_file,
+//!     type_expr::{ObjectField, TypeExpr, TypeInfo},
+//!     DefinitionFileOptions,
+//!     Deps,
+//!     TypeDef,
+//! };
+//!
+//! #[derive(Serialize, TypeDef)]
+//! struct Foo {
+//!     a: String,
+//! }
+//!
+//! struct Wrapper<T> {
+//!     value: T,
+//! }
+//!
+//! impl<T: TypeDef> TypeDef for Wrapper<T> {
+//!     const INFO: TypeInfo = TypeInfo {
+//!         path: &[],
+//!         name: "Wrapper",
+//!         docs: None,
+//!         generic_params: &["T"],
+//!     };
+//!     fn def() -> TypeExpr {
+//!         TypeExpr::Object(vec![ObjectField {
+//!             name: "value".into(),
+//!             optional: false,
+//!             r#type: TypeExpr::TypeVar("T"),
+//!         }])
+//!     }
+//!     fn register_deps(deps: &mut Deps) {
+//!         deps.add::<T>();
+//!     }
+//!     fn generic_args() -> Vec<TypeExpr> {
+//!         if T::INLINE {
+//!             vec![T::def()]
+//!         } else {
+//!             vec![TypeExpr::Ref { info: T::INFO, args: T::generic_args() }]
+//!         }
+//!     }
+//! }
+//!
+//! #[derive(Serialize, TypeDef)]
+//! struct Container {
+//!     w: Wrapper<Foo>,
+//! }
+//!
+//! let ts_module = {
+//!     let mut buf = Vec::new();
+//!     write_definition_file::<_, Container>(&mut buf, Default::default()).unwrap();
+//!     String::from_utf8(buf).unwrap()
+//! };
+//! assert_eq!(
+//!     ts_module,
+//!     r#"// AUTO-GENERATED by typescript-type-def
+//!
+//! export default types;
+//! export namespace types{
+//! export type Foo={"a":string;};
+//! export type Wrapper<T>={"value":T;};
+//! export type Container={"w":types.Wrapper<types.Foo>;};
+//! }
+//! "#
+//! );
+//! ```
+//!
+//! [`DefinitionFileOptions::names`] controls a [`NamePolicy`] that renames
+//! emitted type names and object field keys independently of any
+//! `#[serde(rename)]` already applied to them. [`CasedNames`] forces a
+//! fixed casing and adds a prefix/suffix to type names (folding in the
+//! type's namespace path, if it has one, to tell apart types that share a
+//! bare name across different `#[type_def(namespace = "...")]` paths):
+//! ```
+//! use std::sync::Arc;
+//!
+//! use serde::Serialize;
+//! use typescript_type_def::{
+//!     write_definition_file,
+//!     CasedNames,
+//!     Casing,
+//!     DefinitionFileOptions,
+//!     TypeDef,
+//! };
+//!
+//! #[derive(Serialize, TypeDef)]
+//! struct Request {
+//!     request_id: usize,
+//! }
+//!
+//! let ts_module = {
+//!     let mut buf = Vec::new();
+//!     let options = DefinitionFileOptions {
+//!         names: Arc::new(CasedNames {
+//!             type_casing: Casing::PascalCase,
+//!             field_casing: Casing::CamelCase,
+//!             type_prefix: "".into(),
+//!             type_suffix: "Dto".into(),
+//!         }),
+//!         ..Default::default()
+//!     };
+//!     write_definition_file::<_, Request>(&mut buf, options).unwrap();
+//!     String::from_utf8(buf).unwrap()
+//! };
+//! assert_eq!(
+//!     ts_module,
+//!     r#"// AUTO-GENERATED by typescript-type-def
+//!
+//! export default types;
+//! export namespace types{
+//! export type UsizeDto=number;
+//! export type RequestDto={"requestId":types.UsizeDto;};
+//! }
+//! "#
+//! );
+//! ```
+//!
+//! Two types with the same name placed under different
+//! `#[type_def(namespace = "...")]` paths print as distinct names under
+//! [`CasedNames`], instead of silently colliding:
+//! ```
+//! use std::sync::Arc;
+//!
+//! use serde::Serialize;
+//! use typescript_type_def::{
+//!     write_definition_file,
+//!     CasedNames,
+//!     Casing,
+//!     DefinitionFileOptions,
+//!     TypeDef,
+//! };
+//!
+//! #[derive(Serialize, TypeDef)]
+//! #[type_def(namespace = "billing")]
+//! struct Invoice {
+//!     total: usize,
+//! }
+//!
+//! let ts_module = {
+//!     let mut buf = Vec::new();
+//!     let options = DefinitionFileOptions {
+//!         names: Arc::new(CasedNames {
+//!             type_casing: Casing::PascalCase,
+//!             field_casing: Casing::CamelCase,
+//!             type_prefix: "".into(),
+//!             type_suffix: "".into(),
+//!         }),
+//!         ..Default::default()
+//!     };
+//!     write_definition_file::<_, Invoice>(&mut buf, options).unwrap();
+//!     String::from_utf8(buf).unwrap()
+//! };
+//! assert_eq!(
+//!     ts_module,
+//!     r#"// AUTO-GENERATED by typescript-type-def
+//!
+//! export default types;
+//! export namespace types{
+//! export type Usize=number;
+//! export type BillingInvoice={"total":types.Usize;};
+//! }
+//! "#
+//! );
+//! ```
 #![warn(rust_2018_idioms, clippy::all, missing_docs)]
 #![deny(clippy::correctness)]
 
@@ -108,10 +489,16 @@ pub mod type_expr;
 pub use crate::{
     emit::{
         write_definition_file,
+        write_definition_files,
+        CasedNames,
+        Casing,
         DefinitionFileOptions,
         Deps,
+        IdentityNames,
+        NamePolicy,
         Stats,
         TypeDef,
+        ZodMode,
     },
     impls::Blob,
 };
@@ -132,6 +519,13 @@ pub use crate::{
 ///   the TypeScript type definition under a namespace of `x.y.z`. Note
 ///   that [`write_definition_file`] will additionally place all type
 ///   definitions under a namespace called `types`.
+///
+/// This macro does not currently support generic `struct`s/`enum`s: the
+/// [`TypeDef`] trait and [`TypeExpr`](crate::type_expr::TypeExpr) can
+/// represent a generic type's definition (in terms of
+/// [`TypeExpr::TypeVar`](crate::type_expr::TypeExpr::TypeVar)), but this
+/// macro doesn't yet generate one. A generic type's `TypeDef` impl has to
+/// be written by hand for now; see the crate documentation for an example.
 // TODO: add description of what shapes are generated for various types
 // newtypes, enums, optional struct fields, etc.
 pub use typescript_type_def_derive::TypeDef;
@@ -0,0 +1,147 @@
+//! Data types used to describe the shape of a generated TypeScript type
+//! definition.
+//!
+//! [`TypeDef::def`](crate::TypeDef::def) returns a [`TypeExpr`] tree that
+//! mirrors the JSON shape `serde` would produce for a Rust type. The
+//! [`emit`](crate::emit) module walks this tree to print TypeScript syntax,
+//! and the same tree can be walked to derive other artifacts from it.
+
+use std::borrow::Cow;
+
+/// Identifies a named type definition.
+///
+/// This is used both to print a `types.Foo` reference and as the key types
+/// are deduplicated by in [`Deps`](crate::emit::Deps).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TypeInfo {
+    /// The path of namespaces this definition was declared under (via
+    /// `#[type_def(namespace = "...")]`), not including the root namespace
+    /// [`write_definition_file`](crate::write_definition_file) adds.
+    ///
+    /// Definitions are not emitted as nested namespaces: every definition
+    /// is printed directly under the root namespace, and this path is only
+    /// folded into names by [`CasedNames`](crate::CasedNames) and into
+    /// filenames by [`write_definition_files`](crate::write_definition_files).
+    pub path: &'static [&'static str],
+    /// The name of the type, as it appears after `export type`.
+    pub name: &'static str,
+    /// The doc comment attached to the type, if any, emitted as a
+    /// `/** ... */` block above the definition.
+    pub docs: Option<&'static str>,
+    /// The names of this type's generic parameters, in declaration order
+    /// (e.g. `&["T"]` for `struct Wrapper<T>`), or empty if this type isn't
+    /// generic. Only meaningful on the definition itself, not on a
+    /// [`TypeExpr::Ref`] to it.
+    pub generic_params: &'static [&'static str],
+}
+
+/// The primitive TypeScript types a Rust type's definition can bottom out
+/// at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prim {
+    /// `number`
+    Number,
+    /// `string`
+    String,
+    /// `boolean`
+    Boolean,
+    /// `null`
+    Null,
+    /// `any`
+    Any,
+}
+
+/// A single field of a [`TypeExpr::Object`] or variant payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectField {
+    /// The JSON key used for this field (after any `#[serde(rename)]`).
+    pub name: Cow<'static, str>,
+    /// Whether this field may be absent from the object entirely, as
+    /// opposed to present with a `null` value.
+    pub optional: bool,
+    /// The shape of this field's value.
+    pub r#type: TypeExpr,
+}
+
+/// One variant of a tagged [`TypeExpr::Union`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariantExpr {
+    /// The name of the Rust variant, used to derive handler method names
+    /// (e.g. `onAlert`).
+    pub name: &'static str,
+    /// The value the tag field takes for this variant.
+    pub tag_value: Cow<'static, str>,
+    /// The shape of this variant's payload.
+    pub payload: VariantPayload,
+}
+
+/// The shape carried by a single [`VariantExpr`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VariantPayload {
+    /// A unit variant, which carries no payload beyond the tag itself.
+    Unit,
+    /// A tuple variant with a single element, emitted as a value stored
+    /// under `content` alongside the tag, matching adjacently-tagged
+    /// `#[serde(tag = "...", content = "...")]` output. `content` is the
+    /// enum's actual `content` key, not assumed to be the literal string
+    /// `"content"`.
+    ///
+    /// This only represents *adjacently* tagged output. An *internally*
+    /// tagged (`#[serde(tag = "...")]`, no `content`) enum's newtype
+    /// variant isn't representable as a `VariantPayload` at all: its
+    /// payload's own fields are flattened alongside the tag in the same
+    /// object, which requires the payload to itself be an object shape
+    /// merged into the variant, not a value nested under a key. A
+    /// `TypeDef` impl for an internally tagged enum with a newtype variant
+    /// should not construct this variant; there is currently no tagging
+    /// style this crate can use to describe it.
+    Newtype {
+        /// The JSON key the payload is stored under, alongside the tag.
+        content: Cow<'static, str>,
+        /// The shape of the payload value itself.
+        payload: Box<TypeExpr>,
+    },
+    /// A struct variant, whose fields are merged into the tagged object.
+    Fields(Vec<ObjectField>),
+}
+
+/// The shape of a generated or referenced TypeScript type.
+///
+/// This is the common representation every [`TypeDef`](crate::TypeDef)
+/// implementation (hand-written or derived) produces, and every generation
+/// mode in [`emit`](crate::emit) consumes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeExpr {
+    /// A reference to another named type, printed as `types.Name`, or
+    /// `types.Name<Arg, ...>` when `args` is non-empty.
+    Ref {
+        /// The referenced type's identity.
+        info: TypeInfo,
+        /// The concrete type arguments this reference was instantiated
+        /// with, if the referenced type is generic.
+        args: Vec<TypeExpr>,
+    },
+    /// A reference to one of the enclosing type's own generic parameters
+    /// (e.g. `T` inside `struct Wrapper<T>`), printed as its bare name.
+    TypeVar(&'static str),
+    /// A primitive type.
+    Prim(Prim),
+    /// A JSON object with a fixed set of fields.
+    Object(Vec<ObjectField>),
+    /// A homogeneous array.
+    Array(Box<TypeExpr>),
+    /// A fixed-length heterogeneous array.
+    Tuple(Vec<TypeExpr>),
+    /// A value that may be absent, represented as `T | null`.
+    Option(Box<TypeExpr>),
+    /// A string-keyed map, represented as `Record<K, V>`.
+    Map(Box<TypeExpr>, Box<TypeExpr>),
+    /// A tagged union, as produced by an internally- or adjacently-tagged
+    /// `#[serde(tag = "...")]` enum.
+    Union {
+        /// The JSON key the tag is stored under.
+        tag: Cow<'static, str>,
+        /// The possible shapes of the tagged value, one per Rust variant.
+        variants: Vec<VariantExpr>,
+    },
+}